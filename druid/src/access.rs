@@ -0,0 +1,80 @@
+// Copyright 2021 The druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for exposing the widget tree to assistive technology, via
+//! [AccessKit](https://accesskit.dev).
+
+#![cfg(feature = "accesskit")]
+
+use accesskit::{Action, ActionRequest, NodeBuilder, NodeId};
+
+use crate::{Command, WidgetId};
+
+impl From<WidgetId> for NodeId {
+    fn from(id: WidgetId) -> NodeId {
+        NodeId(id.to_raw())
+    }
+}
+
+/// A context passed to [`Widget::accessibility`] during the accessibility pass.
+///
+/// This pass runs after `layout`, once per frame in which the accessibility
+/// tree is considered dirty. Each widget builds the [`NodeBuilder`] for its
+/// own id and recurses into its children; the platform shell assembles the
+/// per-widget nodes into the tree handed to the AccessKit adapter.
+///
+/// [`Widget::accessibility`]: crate::Widget::accessibility
+pub struct AccessCtx<'a> {
+    pub(crate) widget_id: WidgetId,
+    pub(crate) node_builder: NodeBuilder,
+    pub(crate) action_request: Option<&'a ActionRequest>,
+    pub(crate) commands: Vec<Command>,
+}
+
+impl<'a> AccessCtx<'a> {
+    /// The id of the widget currently being visited.
+    pub fn widget_id(&self) -> WidgetId {
+        self.widget_id
+    }
+
+    /// Mutable access to the node being built for this widget.
+    pub fn node(&mut self) -> &mut NodeBuilder {
+        &mut self.node_builder
+    }
+
+    /// The pending platform action request for this widget, if any.
+    ///
+    /// An AT (assistive technology) sends an `ActionRequest` targeting a
+    /// specific node id; this returns `Some` only when that target is the
+    /// widget currently being visited.
+    pub fn action_request(&self) -> Option<&ActionRequest> {
+        let id: NodeId = self.widget_id.into();
+        self.action_request.filter(|req| req.target == id)
+    }
+
+    /// Returns `true` if the pending action request for this widget is `action`.
+    pub fn is_action_requested(&self, action: Action) -> bool {
+        self.action_request()
+            .map_or(false, |req| req.action == action)
+    }
+
+    /// Queue a command to be submitted once the accessibility pass completes.
+    ///
+    /// Widgets use this to translate an incoming AT action (such as "set
+    /// focus") into the same commands that drive the rest of the framework,
+    /// so the two code paths can't drift apart.
+    pub fn submit_command(&mut self, command: impl Into<Command>) {
+        self.commands.push(command.into());
+    }
+}
@@ -0,0 +1,347 @@
+// Copyright 2020 The druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The context types passed to widgets during each pass over the tree.
+
+use crate::operation::Operation;
+use crate::widget::Direction;
+use crate::{Command, FocusNode, FocusScopeNode, FocusState, Point, WidgetId};
+
+/// Penalty applied to a candidate's perpendicular (off-axis) offset when
+/// scoring it for `EventCtx::focus_direction`. Chosen so that a small
+/// detour along the travel axis is still preferred over a candidate that's
+/// badly misaligned with the current widget.
+const SECONDARY_AXIS_PENALTY: f64 = 2.0;
+
+/// Context given to [`Widget::event`](crate::Widget::event).
+pub struct EventCtx<'a> {
+    pub(crate) widget_id: WidgetId,
+    pub(crate) is_handled: bool,
+    pub(crate) focus_node: FocusNode,
+    pub(crate) focus_scope_node: FocusScopeNode,
+    pub(crate) focus_state: &'a mut FocusState,
+    pub(crate) command_queue: &'a mut Vec<Command>,
+}
+
+/// Context given to [`Widget::lifecycle`](crate::Widget::lifecycle).
+pub struct LifeCycleCtx<'a> {
+    pub(crate) widget_id: WidgetId,
+    pub(crate) focus_node: FocusNode,
+    pub(crate) focus_scope_node: FocusScopeNode,
+    pub(crate) focus_state: &'a mut FocusState,
+    pub(crate) command_queue: &'a mut Vec<Command>,
+    pub(crate) needs_accessibility_update: &'a mut bool,
+}
+
+/// Context given to [`Widget::update`](crate::Widget::update).
+pub struct UpdateCtx<'a> {
+    pub(crate) focus_node: FocusNode,
+    pub(crate) focus_scope_node: FocusScopeNode,
+    pub(crate) focus_state: &'a mut FocusState,
+}
+
+/// Context given to [`Widget::layout`](crate::Widget::layout).
+pub struct LayoutCtx<'a> {
+    pub(crate) focus_node: FocusNode,
+    pub(crate) focus_scope_node: FocusScopeNode,
+    pub(crate) focus_state: &'a mut FocusState,
+    pub(crate) window_origin: Point,
+}
+
+/// Context given to [`Widget::paint`](crate::Widget::paint).
+pub struct PaintCtx<'a> {
+    pub(crate) focus_node: FocusNode,
+    pub(crate) focus_scope_node: FocusScopeNode,
+    pub(crate) focus_state: &'a mut FocusState,
+}
+
+macro_rules! impl_focus_node_accessors {
+    ($ty:ident) => {
+        impl<'a> $ty<'a> {
+            /// The ambient `FocusNode` of the nearest enclosing `Focus` widget.
+            pub fn focus_node(&self) -> FocusNode {
+                self.focus_node
+            }
+
+            pub fn set_focus_node(&mut self, node: FocusNode) {
+                self.focus_node = node;
+            }
+
+            /// The ambient `FocusScopeNode` of the nearest enclosing `FocusScope` widget.
+            pub fn focus_scope(&self) -> FocusScopeNode {
+                self.focus_scope_node
+            }
+
+            pub fn set_focus_scope_node(&mut self, node: FocusScopeNode) {
+                self.focus_scope_node = node;
+            }
+        }
+    };
+}
+
+impl_focus_node_accessors!(EventCtx);
+impl_focus_node_accessors!(LifeCycleCtx);
+impl_focus_node_accessors!(UpdateCtx);
+impl_focus_node_accessors!(LayoutCtx);
+impl_focus_node_accessors!(PaintCtx);
+
+impl<'a> EventCtx<'a> {
+    pub fn widget_id(&self) -> WidgetId {
+        self.widget_id
+    }
+
+    /// Whether a previous widget on this event's path has already handled it.
+    pub fn is_handled(&self) -> bool {
+        self.is_handled
+    }
+
+    pub fn set_handled(&mut self) {
+        self.is_handled = true;
+    }
+
+    /// Requests focus for the current widget.
+    pub fn request_focus(&mut self) {
+        self.focus_state.focused = self.focus_node.widget_id;
+    }
+
+    pub fn request_paint(&mut self) {}
+
+    pub fn submit_command(&mut self, command: impl Into<Command>) {
+        self.command_queue.push(command.into());
+    }
+
+    /// Moves focus to the focusable widget nearest the current one in
+    /// `direction`, using each widget's layout rect in window coordinates.
+    ///
+    /// Candidates are first filtered to the half-plane `direction` points
+    /// into (e.g. for `Right`, only widgets whose center has a greater `x`
+    /// than the current widget's), then scored by
+    /// `primary_axis_distance + SECONDARY_AXIS_PENALTY * secondary_axis_offset`,
+    /// where `primary` is the distance along the travel axis and
+    /// `secondary` is the perpendicular misalignment. The minimum-scoring
+    /// candidate wins; ties leave focus unchanged, and so does an empty
+    /// candidate set.
+    pub fn focus_direction(&mut self, direction: Direction) {
+        let origin = match self
+            .focus_state
+            .focused
+            .and_then(|focused| self.focus_state.chain.iter().find(|n| n.widget_id == Some(focused)))
+            .map(|n| n.rect.center())
+        {
+            Some(origin) => origin,
+            None => return,
+        };
+
+        let mut best: Option<(WidgetId, f64)> = None;
+        let mut best_tied = false;
+        for node in &self.focus_state.chain {
+            let id = match node.widget_id {
+                Some(id) if Some(id) != self.focus_state.focused => id,
+                _ => continue,
+            };
+            let center = node.rect.center();
+            let (primary, secondary, in_half_plane) = match direction {
+                Direction::Right => (center.x - origin.x, center.y - origin.y, center.x > origin.x),
+                Direction::Left => (origin.x - center.x, center.y - origin.y, center.x < origin.x),
+                Direction::Down => (center.y - origin.y, center.x - origin.x, center.y > origin.y),
+                Direction::Up => (origin.y - center.y, center.x - origin.x, center.y < origin.y),
+            };
+            if !in_half_plane {
+                continue;
+            }
+            let score = primary + SECONDARY_AXIS_PENALTY * secondary.abs();
+            match best {
+                Some((_, best_score)) if score < best_score => {
+                    best = Some((id, score));
+                    best_tied = false;
+                }
+                Some((_, best_score)) if score == best_score => {
+                    best_tied = true;
+                }
+                Some(_) => {}
+                None => best = Some((id, score)),
+            }
+        }
+
+        if let Some((id, _)) = best {
+            if !best_tied {
+                self.submit_command(crate::commands::REQUEST_FOCUS.with(id).to(id));
+            }
+        }
+    }
+
+    /// Walks every registered focusable widget, in registration order,
+    /// invoking `op.focusable` for each one. This is what `count_focusables`
+    /// and `focus_first_matching` in [`crate::operation`] are built on.
+    ///
+    /// This pass carries no `Tab`-order semantics: a widget with a negative
+    /// `tab_index` (focusable, but skipped by `Tab`) still appears here.
+    /// `Tab`/`Shift+Tab` instead use [`operate_tab_order`](Self::operate_tab_order).
+    pub fn operate(&mut self, op: &mut impl Operation) {
+        let focused = self.focus_state.focused;
+        for node in &self.focus_state.chain {
+            if let Some(id) = node.widget_id {
+                op.focusable(id, Some(id) == focused, true);
+            }
+        }
+    }
+
+    /// Like [`operate`](Self::operate), but walks widgets in `Tab` order
+    /// instead: sorted by `(tab_index, registration_order)`, with
+    /// negative-`tab_index` widgets excluded entirely. This is what
+    /// `focus_next`/`focus_prev` are built on.
+    pub(crate) fn operate_tab_order(&mut self, op: &mut impl Operation) {
+        let focused = self.focus_state.focused;
+        for node in self.focus_state.tab_order() {
+            if let Some(id) = node.widget_id {
+                op.focusable(id, Some(id) == focused, true);
+            }
+        }
+    }
+}
+
+impl<'a> LifeCycleCtx<'a> {
+    pub fn widget_id(&self) -> WidgetId {
+        self.widget_id
+    }
+
+    /// Registers the current `Focus` node so it participates in Tab
+    /// traversal, spatial navigation, and the `Operation` pass.
+    pub fn register_for_focus(&mut self) {
+        self.focus_state.register(self.focus_node);
+    }
+
+    pub fn request_paint(&mut self) {}
+
+    /// Marks the accessibility tree as dirty, so the next accessibility
+    /// pass picks up whatever changed (e.g. an `is_focused` flag).
+    pub fn request_accessibility_update(&mut self) {
+        *self.needs_accessibility_update = true;
+    }
+
+    pub fn submit_command(&mut self, command: impl Into<Command>) {
+        self.command_queue.push(command.into());
+    }
+}
+
+impl<'a> LayoutCtx<'a> {
+    /// This widget's origin, in window coordinates, accumulated by the
+    /// `WidgetPod`s it's nested inside as layout recurses.
+    pub fn window_origin(&self) -> Point {
+        self.window_origin
+    }
+
+    pub(crate) fn set_window_origin(&mut self, origin: Point) {
+        self.window_origin = origin;
+    }
+
+    /// Refreshes `node`'s entry in the focus chain, e.g. after its `rect`
+    /// has just been computed for this layout pass.
+    pub fn update_focus_node(&mut self, node: FocusNode) {
+        self.focus_state.update(node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{commands, Rect};
+
+    fn registered(id: WidgetId, rect: Rect) -> FocusNode {
+        FocusNode {
+            widget_id: Some(id),
+            is_focused: false,
+            rect,
+            tab_index: FocusNode::UNSET_TAB_INDEX,
+        }
+    }
+
+    fn event_ctx<'a>(
+        focus_state: &'a mut FocusState,
+        command_queue: &'a mut Vec<Command>,
+    ) -> EventCtx<'a> {
+        EventCtx {
+            widget_id: WidgetId::next(),
+            is_handled: false,
+            focus_node: FocusNode::empty(),
+            focus_scope_node: FocusScopeNode { widget_id: None },
+            focus_state,
+            command_queue,
+        }
+    }
+
+    #[test]
+    fn focus_direction_picks_nearest_candidate_in_half_plane() {
+        let current = WidgetId::next();
+        let right_near = WidgetId::next();
+        let right_far = WidgetId::next();
+        let left = WidgetId::next();
+
+        let mut focus_state = FocusState::default();
+        focus_state.chain = vec![
+            registered(current, Rect::new(0.0, 0.0, 10.0, 10.0)),
+            registered(right_near, Rect::new(20.0, 0.0, 30.0, 10.0)),
+            registered(right_far, Rect::new(100.0, 0.0, 110.0, 10.0)),
+            registered(left, Rect::new(-30.0, 0.0, -20.0, 10.0)),
+        ];
+        focus_state.focused = Some(current);
+
+        let mut command_queue = Vec::new();
+        let mut ctx = event_ctx(&mut focus_state, &mut command_queue);
+        ctx.focus_direction(Direction::Right);
+
+        assert_eq!(command_queue.len(), 1);
+        let target = *command_queue[0].get_unchecked(commands::REQUEST_FOCUS);
+        assert_eq!(target, right_near);
+    }
+
+    #[test]
+    fn focus_direction_on_exact_tie_leaves_focus_unchanged() {
+        let current = WidgetId::next();
+        let tie_above = WidgetId::next();
+        let tie_below = WidgetId::next();
+
+        let mut focus_state = FocusState::default();
+        focus_state.chain = vec![
+            registered(current, Rect::new(0.0, 0.0, 10.0, 10.0)),
+            // Both candidates are the same distance along the travel axis
+            // and equally (but oppositely) offset on the secondary axis, so
+            // they score identically under `focus_direction`'s formula.
+            registered(tie_above, Rect::new(20.0, -10.0, 30.0, 0.0)),
+            registered(tie_below, Rect::new(20.0, 10.0, 30.0, 20.0)),
+        ];
+        focus_state.focused = Some(current);
+
+        let mut command_queue = Vec::new();
+        let mut ctx = event_ctx(&mut focus_state, &mut command_queue);
+        ctx.focus_direction(Direction::Right);
+
+        assert!(command_queue.is_empty());
+    }
+
+    #[test]
+    fn focus_direction_with_no_candidates_leaves_focus_unchanged() {
+        let current = WidgetId::next();
+
+        let mut focus_state = FocusState::default();
+        focus_state.chain = vec![registered(current, Rect::new(0.0, 0.0, 10.0, 10.0))];
+        focus_state.focused = Some(current);
+
+        let mut command_queue = Vec::new();
+        let mut ctx = event_ctx(&mut focus_state, &mut command_queue);
+        ctx.focus_direction(Direction::Right);
+
+        assert!(command_queue.is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2020 The druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The core `Widget` trait, and `WidgetPod`, the wrapper that drives it.
+
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Rect, Size, UpdateCtx,
+};
+
+#[cfg(feature = "accesskit")]
+use crate::AccessCtx;
+
+/// The trait implemented by all widgets.
+pub trait Widget<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env);
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env);
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env);
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size;
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env);
+
+    /// Build this widget's node in the accessibility tree.
+    ///
+    /// The default implementation does nothing, so existing widgets don't
+    /// need to change to keep compiling; widgets that care about exposing
+    /// themselves to assistive technology (like `Focus`/`FocusScope`)
+    /// override it.
+    #[cfg(feature = "accesskit")]
+    #[allow(unused_variables)]
+    fn accessibility(&mut self, ctx: &mut AccessCtx, data: &T, env: &Env) {}
+}
+
+impl<T> Widget<T> for Box<dyn Widget<T>> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        (**self).event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        (**self).lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        (**self).update(ctx, old_data, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        (**self).layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        (**self).paint(ctx, data, env)
+    }
+
+    #[cfg(feature = "accesskit")]
+    fn accessibility(&mut self, ctx: &mut AccessCtx, data: &T, env: &Env) {
+        (**self).accessibility(ctx, data, env)
+    }
+}
+
+/// A wrapper around a widget, holding the state (such as its layout rect and
+/// its previous data, for diffing in `update`) needed to pass contexts down
+/// to it.
+pub struct WidgetPod<T, W> {
+    inner: W,
+    layout_rect: Rect,
+    old_data: Option<T>,
+}
+
+impl<T, W> WidgetPod<T, W> {
+    pub fn new(inner: W) -> Self {
+        WidgetPod {
+            inner,
+            layout_rect: Rect::ZERO,
+            old_data: None,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T> + 'static> WidgetPod<T, W> {
+    /// Box the inner widget, so this pod can be stored as `WidgetPod<T, Box<dyn Widget<T>>>`.
+    pub fn boxed(self) -> WidgetPod<T, Box<dyn Widget<T>>> {
+        WidgetPod {
+            inner: Box::new(self.inner),
+            layout_rect: self.layout_rect,
+            old_data: self.old_data,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
+    pub fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    pub fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    pub fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        if let Some(old_data) = self.old_data.take() {
+            if !old_data.same(data) {
+                self.inner.update(ctx, &old_data, data, env);
+            }
+        }
+        self.old_data = Some(data.clone());
+    }
+
+    pub fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let previous_origin = ctx.window_origin();
+        ctx.set_window_origin(previous_origin + self.layout_rect.origin().to_vec2());
+        let size = self.inner.layout(ctx, bc, data, env);
+        ctx.set_window_origin(previous_origin);
+        size
+    }
+
+    /// Place this pod's child at `rect`, in its parent's coordinate space.
+    pub fn set_layout_rect(&mut self, _ctx: &mut LayoutCtx, _data: &T, _env: &Env, rect: Rect) {
+        self.layout_rect = rect;
+    }
+
+    pub fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    #[cfg(feature = "accesskit")]
+    pub fn accessibility(&mut self, ctx: &mut AccessCtx, data: &T, env: &Env) {
+        self.inner.accessibility(ctx, data, env);
+    }
+}
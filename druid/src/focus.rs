@@ -0,0 +1,177 @@
+// Copyright 2020 The druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Focus bookkeeping shared between the `Focus`/`FocusScope` widgets and the
+//! focus-related methods on [`EventCtx`](crate::EventCtx) and
+//! [`LifeCycleCtx`](crate::LifeCycleCtx).
+
+use crate::{Rect, WidgetId};
+
+/// The registration state of a single `Focus` widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusNode {
+    pub widget_id: Option<WidgetId>,
+    pub is_focused: bool,
+    /// This node's bounding rect in window coordinates, refreshed after
+    /// every layout pass. Used for arrow-key spatial navigation.
+    pub rect: Rect,
+    /// Explicit `Tab`-order priority; see `Focus::with_tab_index`.
+    ///
+    /// [`FocusNode::UNSET_TAB_INDEX`] means "no explicit index was set".
+    /// A negative index is focusable (e.g. via `REQUEST_FOCUS` or
+    /// `Operation`) but is skipped by `Tab`/`Shift+Tab`.
+    pub tab_index: i32,
+}
+
+impl FocusNode {
+    /// Sentinel for "no explicit tab index was set". Such nodes sort after
+    /// every explicitly-indexed node, in registration order.
+    pub const UNSET_TAB_INDEX: i32 = i32::MIN;
+
+    /// A `FocusNode` for a widget that hasn't been added to the tree yet.
+    pub fn empty() -> Self {
+        FocusNode {
+            widget_id: None,
+            is_focused: false,
+            rect: Rect::ZERO,
+            tab_index: Self::UNSET_TAB_INDEX,
+        }
+    }
+}
+
+impl Default for FocusNode {
+    fn default() -> Self {
+        FocusNode::empty()
+    }
+}
+
+/// The registration state of a single `FocusScope` widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusScopeNode {
+    pub widget_id: Option<WidgetId>,
+}
+
+/// Focus bookkeeping for one window, shared by all contexts during a pass
+/// over the tree.
+///
+/// `Focus` widgets register themselves here (via `register_for_focus`)
+/// during `LifeCycle::WidgetAdded`, and refresh their entry after every
+/// layout pass. `EventCtx::focus_direction`, the `Operation` pass in
+/// [`crate::operation`], and `Tab`/`Shift+Tab` handling all read this list
+/// rather than walking the widget tree themselves.
+#[derive(Default)]
+pub struct FocusState {
+    /// Registered focusable nodes, in registration order.
+    pub(crate) chain: Vec<FocusNode>,
+    pub(crate) focused: Option<WidgetId>,
+}
+
+impl FocusState {
+    pub(crate) fn register(&mut self, node: FocusNode) {
+        self.chain.push(node);
+    }
+
+    /// Updates the registered copy of `node`, matched by widget id. Used
+    /// when a node's `is_focused` flag (or, once layout has run, its rect)
+    /// changes after registration.
+    pub(crate) fn update(&mut self, node: FocusNode) {
+        if let Some(existing) = self
+            .chain
+            .iter_mut()
+            .find(|existing| existing.widget_id == node.widget_id)
+        {
+            *existing = node;
+        }
+    }
+
+    /// The nodes `Tab`/`Shift+Tab` should cycle through: every registered
+    /// node with a non-negative (or unset) `tab_index`, sorted by
+    /// `(tab_index, registration_order)`. A negative `tab_index` is
+    /// dropped here, opting the node out of `Tab` entirely while leaving it
+    /// reachable through `Operation`/`REQUEST_FOCUS`.
+    pub(crate) fn tab_order(&self) -> Vec<&FocusNode> {
+        let mut nodes: Vec<&FocusNode> = self
+            .chain
+            .iter()
+            .filter(|node| node.tab_index >= 0 || node.tab_index == FocusNode::UNSET_TAB_INDEX)
+            .collect();
+        // `sort_by_key` is stable, so nodes that compare equal (including
+        // every unset node) keep their relative registration order.
+        nodes.sort_by_key(|node| {
+            if node.tab_index == FocusNode::UNSET_TAB_INDEX {
+                (1, 0)
+            } else {
+                (0, node.tab_index)
+            }
+        });
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registered(id: WidgetId, tab_index: i32) -> FocusNode {
+        FocusNode {
+            widget_id: Some(id),
+            is_focused: false,
+            rect: Rect::ZERO,
+            tab_index,
+        }
+    }
+
+    #[test]
+    fn tab_order_sorts_by_tab_index_then_registration_order() {
+        let unset_first = WidgetId::next();
+        let explicit_two = WidgetId::next();
+        let explicit_one = WidgetId::next();
+        let unset_second = WidgetId::next();
+
+        let mut state = FocusState::default();
+        state.register(registered(unset_first, FocusNode::UNSET_TAB_INDEX));
+        state.register(registered(explicit_two, 2));
+        state.register(registered(explicit_one, 1));
+        state.register(registered(unset_second, FocusNode::UNSET_TAB_INDEX));
+
+        let order: Vec<WidgetId> = state
+            .tab_order()
+            .into_iter()
+            .map(|node| node.widget_id.unwrap())
+            .collect();
+
+        assert_eq!(
+            order,
+            vec![explicit_one, explicit_two, unset_first, unset_second]
+        );
+    }
+
+    #[test]
+    fn tab_order_drops_negative_tab_index_nodes() {
+        let visible = WidgetId::next();
+        let hidden = WidgetId::next();
+
+        let mut state = FocusState::default();
+        state.register(registered(hidden, -1));
+        state.register(registered(visible, FocusNode::UNSET_TAB_INDEX));
+
+        let order: Vec<WidgetId> = state
+            .tab_order()
+            .into_iter()
+            .map(|node| node.widget_id.unwrap())
+            .collect();
+
+        assert_eq!(order, vec![visible]);
+    }
+}
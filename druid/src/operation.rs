@@ -0,0 +1,236 @@
+// Copyright 2021 The druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A traversal-operation pass over the widget tree.
+//!
+//! This lets application code inspect or redirect focus without owning
+//! widget ids up front: submit an [`Operation`] via [`EventCtx::operate`]
+//! and it is invoked once per registered `Focus` node, walking the window's
+//! focus chain rather than the widget tree.
+
+use crate::{commands, EventCtx, WidgetId};
+
+/// A callback invoked once per focusable widget during an [`EventCtx::operate`]
+/// pass.
+pub trait Operation {
+    /// Called for each `Focus` node encountered during the walk, in
+    /// registration order. `is_focusable` is `true` for every node: `Tab`
+    /// order (which can skip a node with a negative `tab_index`) is a
+    /// separate concern handled by [`focus_next`]/[`focus_prev`] via
+    /// `EventCtx::operate_tab_order`, not by this generic pass.
+    fn focusable(&mut self, id: WidgetId, is_focused: bool, is_focusable: bool);
+}
+
+/// Finds the focusable widget immediately before or after the currently
+/// focused one, in traversal order, wrapping around at either end.
+///
+/// This is the operation [`focus_next`] and [`focus_prev`] are built on.
+#[derive(Default)]
+struct FocusStep {
+    reverse: bool,
+    first: Option<WidgetId>,
+    last: Option<WidgetId>,
+    before_focused: Option<WidgetId>,
+    after_focused: Option<WidgetId>,
+    seen_focused: bool,
+}
+
+impl Operation for FocusStep {
+    fn focusable(&mut self, id: WidgetId, is_focused: bool, is_focusable: bool) {
+        if !is_focusable {
+            return;
+        }
+        if self.first.is_none() {
+            self.first = Some(id);
+        }
+        self.last = Some(id);
+        if self.seen_focused && self.after_focused.is_none() {
+            self.after_focused = Some(id);
+        }
+        if is_focused {
+            self.seen_focused = true;
+        } else if !self.seen_focused {
+            self.before_focused = Some(id);
+        }
+    }
+}
+
+impl FocusStep {
+    fn target(&self) -> Option<WidgetId> {
+        if self.reverse {
+            self.before_focused.or(self.last)
+        } else {
+            self.after_focused.or(self.first)
+        }
+    }
+}
+
+/// Moves focus to the next focusable widget in traversal order, wrapping
+/// around to the first one.
+pub fn focus_next(ctx: &mut EventCtx) {
+    let mut op = FocusStep {
+        reverse: false,
+        ..Default::default()
+    };
+    ctx.operate_tab_order(&mut op);
+    if let Some(id) = op.target() {
+        ctx.submit_command(commands::REQUEST_FOCUS.with(id).to(id));
+    }
+}
+
+/// Moves focus to the previous focusable widget in traversal order, wrapping
+/// around to the last one.
+pub fn focus_prev(ctx: &mut EventCtx) {
+    let mut op = FocusStep {
+        reverse: true,
+        ..Default::default()
+    };
+    ctx.operate_tab_order(&mut op);
+    if let Some(id) = op.target() {
+        ctx.submit_command(commands::REQUEST_FOCUS.with(id).to(id));
+    }
+}
+
+/// Counts the focusable widgets currently in the tree.
+#[derive(Default)]
+struct CountFocusables(usize);
+
+impl Operation for CountFocusables {
+    fn focusable(&mut self, _id: WidgetId, _is_focused: bool, is_focusable: bool) {
+        if is_focusable {
+            self.0 += 1;
+        }
+    }
+}
+
+/// Counts the focusable widgets currently in the tree.
+pub fn count_focusables(ctx: &mut EventCtx) -> usize {
+    let mut op = CountFocusables::default();
+    ctx.operate(&mut op);
+    op.0
+}
+
+/// Finds the first focusable widget for which `predicate` returns `true`.
+struct FocusFirstMatching<F> {
+    predicate: F,
+    found: Option<WidgetId>,
+}
+
+impl<F: FnMut(WidgetId) -> bool> Operation for FocusFirstMatching<F> {
+    fn focusable(&mut self, id: WidgetId, _is_focused: bool, is_focusable: bool) {
+        if self.found.is_none() && is_focusable && (self.predicate)(id) {
+            self.found = Some(id);
+        }
+    }
+}
+
+/// Requests focus on the first focusable widget matching `predicate`.
+///
+/// This lets features like "focus the first invalid field" or "move focus
+/// into a newly opened panel" be expressed without hard-coding a target
+/// widget id: the caller submits a predicate over whatever extra state it
+/// tracks per widget, and this walks the registered focus chain to find it.
+pub fn focus_first_matching(ctx: &mut EventCtx, predicate: impl FnMut(WidgetId) -> bool) {
+    let mut op = FocusFirstMatching {
+        predicate,
+        found: None,
+    };
+    ctx.operate(&mut op);
+    if let Some(id) = op.found {
+        ctx.submit_command(commands::REQUEST_FOCUS.with(id).to(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Command, FocusNode, FocusScopeNode, FocusState, Rect};
+
+    fn registered(id: WidgetId, tab_index: i32) -> FocusNode {
+        FocusNode {
+            widget_id: Some(id),
+            is_focused: false,
+            rect: Rect::ZERO,
+            tab_index,
+        }
+    }
+
+    fn event_ctx<'a>(
+        focus_state: &'a mut FocusState,
+        command_queue: &'a mut Vec<Command>,
+    ) -> EventCtx<'a> {
+        EventCtx {
+            widget_id: WidgetId::next(),
+            is_handled: false,
+            focus_node: FocusNode::empty(),
+            focus_scope_node: FocusScopeNode { widget_id: None },
+            focus_state,
+            command_queue,
+        }
+    }
+
+    #[test]
+    fn focus_next_wraps_and_skips_negative_tab_index() {
+        let first = WidgetId::next();
+        let skipped = WidgetId::next();
+        let last = WidgetId::next();
+
+        let mut focus_state = FocusState::default();
+        focus_state.register(registered(first, FocusNode::UNSET_TAB_INDEX));
+        focus_state.register(registered(skipped, -1));
+        focus_state.register(registered(last, FocusNode::UNSET_TAB_INDEX));
+        focus_state.focused = Some(last);
+
+        let mut command_queue = Vec::new();
+        let mut ctx = event_ctx(&mut focus_state, &mut command_queue);
+        focus_next(&mut ctx);
+
+        assert_eq!(command_queue.len(), 1);
+        let target = *command_queue[0].get_unchecked(commands::REQUEST_FOCUS);
+        assert_eq!(target, first);
+    }
+
+    #[test]
+    fn focus_prev_wraps_and_skips_negative_tab_index() {
+        let first = WidgetId::next();
+        let skipped = WidgetId::next();
+        let last = WidgetId::next();
+
+        let mut focus_state = FocusState::default();
+        focus_state.register(registered(first, FocusNode::UNSET_TAB_INDEX));
+        focus_state.register(registered(skipped, -1));
+        focus_state.register(registered(last, FocusNode::UNSET_TAB_INDEX));
+        focus_state.focused = Some(first);
+
+        let mut command_queue = Vec::new();
+        let mut ctx = event_ctx(&mut focus_state, &mut command_queue);
+        focus_prev(&mut ctx);
+
+        assert_eq!(command_queue.len(), 1);
+        let target = *command_queue[0].get_unchecked(commands::REQUEST_FOCUS);
+        assert_eq!(target, last);
+    }
+
+    #[test]
+    fn count_focusables_sees_negative_tab_index_nodes() {
+        let mut focus_state = FocusState::default();
+        focus_state.register(registered(WidgetId::next(), FocusNode::UNSET_TAB_INDEX));
+        focus_state.register(registered(WidgetId::next(), -1));
+
+        let mut command_queue = Vec::new();
+        let mut ctx = event_ctx(&mut focus_state, &mut command_queue);
+
+        assert_eq!(count_focusables(&mut ctx), 2);
+    }
+}
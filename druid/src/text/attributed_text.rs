@@ -0,0 +1,152 @@
+// Copyright 2021 The druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rich text: a plain string plus style spans layered on top of it.
+
+use std::ops::Range;
+
+use super::TextBuffer;
+use crate::piet::{FontStyle, FontWeight, TextAttribute};
+use crate::Color;
+
+/// A single style override applied to a range of text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Attribute {
+    /// Overrides the font family for this range.
+    FontFamily(String),
+    /// Overrides the font size, in points, for this range.
+    FontSize(f64),
+    /// Overrides the font weight for this range.
+    Weight(FontWeight),
+    /// Italicizes (or un-italicizes) this range.
+    Italic(bool),
+    /// Overrides the foreground color for this range.
+    TextColor(Color),
+}
+
+impl Attribute {
+    fn to_piet(&self) -> TextAttribute {
+        match self {
+            Attribute::FontFamily(name) => TextAttribute::FontFamily(name.as_str().into()),
+            Attribute::FontSize(size) => TextAttribute::FontSize(*size),
+            Attribute::Weight(weight) => TextAttribute::Weight(*weight),
+            Attribute::Italic(true) => TextAttribute::Style(FontStyle::Italic),
+            Attribute::Italic(false) => TextAttribute::Style(FontStyle::Regular),
+            Attribute::TextColor(color) => TextAttribute::TextColor(color.clone()),
+        }
+    }
+}
+
+/// A string along with the style spans to apply when it is laid out.
+///
+/// This is the input to [`TextLayout`], and is what makes bold words, inline
+/// color, or mixed sizes within a single line possible; a plain [`TextBuffer`]
+/// can only ever resolve to one font, size, and color for its whole run.
+///
+/// [`TextLayout`]: super::TextLayout
+#[derive(Debug, Clone, Default)]
+pub struct AttributedText {
+    buffer: TextBuffer,
+    spans: Vec<(Range<usize>, Attribute)>,
+}
+
+impl AttributedText {
+    /// Create a new `AttributedText` with no styling.
+    pub fn new(buffer: impl Into<TextBuffer>) -> Self {
+        AttributedText {
+            buffer: buffer.into(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to apply `attribute` to the given byte `range`.
+    pub fn with_attribute(mut self, range: Range<usize>, attribute: Attribute) -> Self {
+        self.spans.push((range, attribute));
+        self
+    }
+
+    /// The underlying, unstyled text.
+    pub fn buffer(&self) -> &TextBuffer {
+        &self.buffer
+    }
+
+    /// The style spans applied on top of [`buffer`](Self::buffer).
+    pub fn spans(&self) -> &[(Range<usize>, Attribute)] {
+        &self.spans
+    }
+
+    pub(crate) fn piet_spans(&self) -> impl Iterator<Item = (Range<usize>, TextAttribute)> + '_ {
+        self.spans
+            .iter()
+            .map(|(range, attribute)| (range.clone(), attribute.to_piet()))
+    }
+
+    /// Returns `true` if a single [`Attribute::TextColor`] span covers the
+    /// whole buffer, meaning the layout never falls back to a default color.
+    pub(crate) fn has_full_color_coverage(&self) -> bool {
+        let len = self.buffer.len();
+        self.spans
+            .iter()
+            .any(|(range, attr)| matches!(attr, Attribute::TextColor(_)) && *range == (0..len))
+    }
+}
+
+impl From<TextBuffer> for AttributedText {
+    fn from(buffer: TextBuffer) -> Self {
+        AttributedText::new(buffer)
+    }
+}
+
+impl From<&str> for AttributedText {
+    fn from(s: &str) -> Self {
+        AttributedText::new(TextBuffer::from(s))
+    }
+}
+
+impl From<String> for AttributedText {
+    fn from(s: String) -> Self {
+        AttributedText::new(TextBuffer::from(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_spans_has_no_full_color_coverage() {
+        let text = AttributedText::from("hello");
+        assert!(!text.has_full_color_coverage());
+    }
+
+    #[test]
+    fn partial_color_span_has_no_full_color_coverage() {
+        let text =
+            AttributedText::from("hello").with_attribute(0..3, Attribute::TextColor(Color::BLACK));
+        assert!(!text.has_full_color_coverage());
+    }
+
+    #[test]
+    fn full_range_color_span_has_full_color_coverage() {
+        let text =
+            AttributedText::from("hello").with_attribute(0..5, Attribute::TextColor(Color::BLACK));
+        assert!(text.has_full_color_coverage());
+    }
+
+    #[test]
+    fn full_range_non_color_span_has_no_full_color_coverage() {
+        let text = AttributedText::from("hello").with_attribute(0..5, Attribute::Italic(true));
+        assert!(!text.has_full_color_coverage());
+    }
+}
@@ -14,14 +14,17 @@
 
 //! A type for representing text that is displayed on the screen.
 
-use super::TextBuffer;
+use std::ops::Range;
+
+use super::{AttributedText, TextBuffer};
 use crate::piet::{
-    FontBuilder as _, PietText, PietTextLayout, Text as _, TextLayout as _, TextLayoutBuilder as _,
+    FontBuilder as _, HitTestPoint, LineMetric, PietText, PietTextLayout, Text as _,
+    TextLayout as _, TextLayoutBuilder as _,
 };
-use crate::{theme, Env, PaintCtx, Point, RenderContext, Size};
+use crate::{theme, Color, Env, PaintCtx, Point, Rect, RenderContext, Size};
 
 pub struct TextLayout {
-    buffer: TextBuffer,
+    buffer: AttributedText,
     // this is optional so that you can create a `TextLayout` before you get passed contexts etc
     layout: Option<PietTextLayout>,
     /// The width for the purpose of line breaks; that is, the width of the view,
@@ -31,11 +34,12 @@ pub struct TextLayout {
 
 impl TextLayout {
     pub fn new(
-        buffer: TextBuffer,
+        buffer: impl Into<AttributedText>,
         text: &mut PietText,
         env: &Env,
         width: impl Into<Option<f64>>,
     ) -> Self {
+        let buffer = buffer.into();
         let width = width.into().unwrap_or(f64::INFINITY);
         let layout = layout_for_buffer(&buffer, text, env, width);
         TextLayout {
@@ -45,7 +49,13 @@ impl TextLayout {
         }
     }
 
-    pub fn update_buffer(&mut self, buffer: TextBuffer, text: &mut PietText, env: &Env) {
+    pub fn update_buffer(
+        &mut self,
+        buffer: impl Into<AttributedText>,
+        text: &mut PietText,
+        env: &Env,
+    ) {
+        let buffer = buffer.into();
         self.layout = layout_for_buffer(&buffer, text, env, self.width);
         self.buffer = buffer;
     }
@@ -59,7 +69,13 @@ impl TextLayout {
 
     pub fn draw(&self, ctx: &mut PaintCtx, point: impl Into<Point>, env: &Env) {
         if let Some(layout) = &self.layout {
-            let color = env.get(theme::LABEL_COLOR);
+            // When every byte already has an explicit per-span color, the
+            // theme's default never shows through, so don't bother reading it.
+            let color = if self.buffer.has_full_color_coverage() {
+                Color::BLACK
+            } else {
+                env.get(theme::LABEL_COLOR)
+            };
             eprintln!("drawing text");
             ctx.draw_text(layout, point, &color);
         }
@@ -77,10 +93,52 @@ impl TextLayout {
             Size::ZERO
         }
     }
+
+    /// Given a point, returns the corresponding byte offset into the buffer,
+    /// or `None` if there is no layout yet.
+    ///
+    /// The offset is clamped to the nearest grapheme boundary under the
+    /// point; it is not guaranteed to fall inside the layout's visible
+    /// bounds, matching piet's `hit_test_point` behaviour for points outside
+    /// the text.
+    pub fn hit_test_point(&self, point: Point) -> Option<usize> {
+        let layout = self.layout.as_ref()?;
+        let HitTestPoint { idx, .. } = layout.hit_test_point(point);
+        Some(idx)
+    }
+
+    /// The point, relative to the layout's origin, at which a caret for the
+    /// given byte offset should be drawn.
+    pub fn point_for_text_position(&self, offset: usize) -> Point {
+        self.layout
+            .as_ref()
+            .map(|layout| layout.hit_test_text_position(offset).point)
+            .unwrap_or_default()
+    }
+
+    /// Returns the rectangles covering the given byte range, for drawing a
+    /// selection highlight. A range spanning multiple lines produces one
+    /// rectangle per line.
+    pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
+        self.layout
+            .as_ref()
+            .map(|layout| layout.rects_for_range(range))
+            .unwrap_or_default()
+    }
+
+    /// The [`LineMetric`] of the line containing the given byte offset, used
+    /// to size a caret for multi-line text.
+    ///
+    /// [`LineMetric`]: crate::piet::LineMetric
+    pub fn line_metric_for_position(&self, offset: usize) -> Option<LineMetric> {
+        let layout = self.layout.as_ref()?;
+        let line_number = layout.line_number_for_text_position(offset);
+        layout.line_metric(line_number)
+    }
 }
 
 fn layout_for_buffer(
-    buffer: &TextBuffer,
+    buffer: &AttributedText,
     text: &mut PietText,
     env: &Env,
     width: f64,
@@ -89,17 +147,46 @@ fn layout_for_buffer(
     let font_name = env.get(theme::FONT_NAME);
     let font_size = env.get(theme::TEXT_SIZE_NORMAL);
     let font = text.new_font_by_name(font_name, font_size).build().ok()?;
-    text.new_text_layout(&font, buffer.slice(..).as_ref(), width)
-        .build()
-        .ok()
+    let mut builder = text.new_text_layout(&font, buffer.buffer().slice(..).as_ref(), width);
+    for (range, attribute) in buffer.piet_spans() {
+        builder = builder.range_attribute(range, attribute);
+    }
+    builder.build().ok()
 }
 
 impl Default for TextLayout {
     fn default() -> Self {
         TextLayout {
-            buffer: TextBuffer::default(),
+            buffer: AttributedText::default(),
             width: f64::INFINITY,
             layout: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `TextLayout::default()` has no `PietTextLayout` (it's only built once
+    // contexts are available), so these exercise the `None` fallback paths
+    // that otherwise have no test coverage.
+
+    #[test]
+    fn hit_test_point_with_no_layout_is_none() {
+        let layout = TextLayout::default();
+        assert_eq!(layout.hit_test_point(Point::ZERO), None);
+    }
+
+    #[test]
+    fn point_for_text_position_with_no_layout_is_origin() {
+        let layout = TextLayout::default();
+        assert_eq!(layout.point_for_text_position(0), Point::default());
+    }
+
+    #[test]
+    fn rects_for_range_with_no_layout_is_empty() {
+        let layout = TextLayout::default();
+        assert!(layout.rects_for_range(0..1).is_empty());
+    }
+}
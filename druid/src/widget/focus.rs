@@ -16,10 +16,28 @@
 
 use druid::widget::prelude::*;
 
+#[cfg(feature = "accesskit")]
 use druid::{
-    commands, Data, FocusNode, HotKey, KbKey, Point, Rect, SysMods, Widget, WidgetPod,
+    accesskit::{Action, Role},
+    AccessCtx,
+};
+use druid::{
+    commands, operation, Data, FocusNode, HotKey, KbKey, Point, Rect, SysMods, Widget, WidgetPod,
 };
 
+/// A direction for spatial (arrow-key) focus navigation.
+///
+/// Unlike `focus_next`/`focus_prev`, which walk the Tab order, these follow
+/// the on-screen layout: pressing `Right` moves focus to the nearest
+/// focusable widget to the right of the current one, and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
 /// A widget that allow focus to be given to this widget and its descendants.
 pub struct Focus<T> {
     child: WidgetPod<T, Box<dyn Widget<T>>>,
@@ -46,6 +64,18 @@ impl<T: Data> Focus<T> {
         self.auto_focus = auto_focus;
         self
     }
+
+    /// Builder-style method to set an explicit tab index, mirroring HTML's
+    /// `tabindex`: `Tab`/`Shift+Tab` visit widgets in ascending order of
+    /// `tab_index` before falling back to registration order for widgets
+    /// that didn't set one.
+    ///
+    /// A negative index is focusable programmatically (e.g. via
+    /// `REQUEST_FOCUS`) but is skipped by `Tab` traversal.
+    pub fn with_tab_index(mut self, tab_index: i32) -> Self {
+        self.focus_node.tab_index = tab_index;
+        self
+    }
 }
 
 impl<T: Data> Widget<T> for Focus<T> {
@@ -67,12 +97,27 @@ impl<T: Data> Widget<T> for Focus<T> {
             }
             Event::KeyDown(key_event) if !ctx.is_handled => {
                 match key_event {
-                    // Tab and shift+tab
+                    // Tab and shift+tab, driven by the `Operation` pass so
+                    // that `ctx.operate_tab_order` has a single, shared
+                    // implementation of "what does Tab order mean".
                     k_e if HotKey::new(None, KbKey::Tab).matches(k_e) => {
-                        ctx.focus_next();
+                        operation::focus_next(ctx);
                     }
                     k_e if HotKey::new(SysMods::Shift, KbKey::Tab).matches(k_e) => {
-                        ctx.focus_prev();
+                        operation::focus_prev(ctx);
+                    }
+                    // Arrow keys: spatial navigation for grid-like layouts.
+                    k_e if HotKey::new(None, KbKey::ArrowUp).matches(k_e) => {
+                        ctx.focus_direction(Direction::Up);
+                    }
+                    k_e if HotKey::new(None, KbKey::ArrowDown).matches(k_e) => {
+                        ctx.focus_direction(Direction::Down);
+                    }
+                    k_e if HotKey::new(None, KbKey::ArrowLeft).matches(k_e) => {
+                        ctx.focus_direction(Direction::Left);
+                    }
+                    k_e if HotKey::new(None, KbKey::ArrowRight).matches(k_e) => {
+                        ctx.focus_direction(Direction::Right);
                     }
                     _ => (),
                 };
@@ -111,6 +156,9 @@ impl<T: Data> Widget<T> for Focus<T> {
                         .to(ctx.widget_id()),
                 );
                 ctx.request_paint();
+                // Let the accessibility tree pick up the new `is_focused` flag so
+                // a screen reader announces focus movement from Tab/Shift+Tab.
+                ctx.request_accessibility_update();
             }
             _ => (),
         }
@@ -133,6 +181,12 @@ impl<T: Data> Widget<T> for Focus<T> {
         let size = self.child.layout(ctx, &bc, data, env);
         let rect = Rect::from_origin_size(Point::ORIGIN, size);
         self.child.set_layout_rect(ctx, data, env, rect);
+
+        // Record this node's rect in window coordinates, so arrow-key
+        // spatial navigation has something to score candidates against.
+        self.focus_node.rect = Rect::from_origin_size(ctx.window_origin(), size);
+        ctx.update_focus_node(self.focus_node);
+
         ctx.set_focus_node(previous_focus_node);
 
         size
@@ -144,4 +198,22 @@ impl<T: Data> Widget<T> for Focus<T> {
         self.child.paint(ctx, data, env);
         ctx.set_focus_node(previous_focus_node);
     }
+
+    #[cfg(feature = "accesskit")]
+    fn accessibility(&mut self, ctx: &mut AccessCtx, data: &T, env: &Env) {
+        let node = ctx.node();
+        node.set_role(Role::Unknown);
+        node.set_focusable(true);
+        if self.focus_node.is_focused {
+            node.set_focused();
+        }
+
+        // An AT-initiated focus request funnels through the same command as
+        // a programmatic `REQUEST_FOCUS`, so the two code paths can't drift.
+        if ctx.is_action_requested(Action::Focus) {
+            ctx.submit_command(commands::REQUEST_FOCUS.with(ctx.widget_id()).to(ctx.widget_id()));
+        }
+
+        self.child.accessibility(ctx, data, env);
+    }
 }
@@ -15,6 +15,9 @@
 //! A focus scope widget.
 
 use druid::widget::prelude::*;
+
+#[cfg(feature = "accesskit")]
+use druid::{accesskit::Role, AccessCtx};
 use druid::{Data, FocusScopeNode, Point, Rect, Widget, WidgetPod};
 
 /// A Widget that serves as a scope for its descendants,
@@ -78,4 +81,13 @@ impl<T: Data> Widget<T> for FocusScope<T> {
         self.child.paint(ctx, data, env);
         ctx.set_focus_scope_node(previous_focus_scope);
     }
+
+    #[cfg(feature = "accesskit")]
+    fn accessibility(&mut self, ctx: &mut AccessCtx, data: &T, env: &Env) {
+        // Expose the scope boundary as a group, so a screen reader can tell
+        // where focus traversal is fenced in rather than seeing a flat list
+        // of the scope's descendants.
+        ctx.node().set_role(Role::Group);
+        self.child.accessibility(ctx, data, env);
+    }
 }